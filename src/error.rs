@@ -0,0 +1,19 @@
+/// Errors produced by [`crate::Lexer::try_next_token`] and [`crate::lex`],
+/// each carrying the byte offset in the input where the problem was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LexError {
+    /// A `"..."` string literal was never closed before the input ended.
+    UnterminatedString { position: usize },
+
+    /// A specific character was required to complete a token, but a
+    /// different one was found instead (`actual` is `'\0'` at EOF).
+    ExpectedCharacter {
+        expected: char,
+        actual: char,
+        position: usize,
+    },
+
+    /// The input ended before a multi-char construct (e.g. a block comment)
+    /// could be closed.
+    UnexpectedEndOfFile { position: usize },
+}
@@ -1,66 +1,210 @@
 #![allow(unused)]
 
 use core::panic;
-use std::path::Iter;
+use error::LexError;
+use std::str::Chars;
+use token::Span;
 use token::Token;
 use token::TokenKind;
+use unicode_xid::UnicodeXID;
 
+mod error;
 mod token;
 
 pub struct Lexer<'a> {
     input: &'a str,
-    /// current position in input (points to current char)
+    /// the remaining input, one char ahead of `ch`
+    chars: Chars<'a>,
+    /// byte offset in input (points to current char)
     position: usize,
-    /// current reading position in input (after current char)
+    /// byte offset in input (after current char)
     read_position: usize,
     /// current char under examination
     ch: char,
+    /// whether comments are emitted as tokens instead of being skipped
+    with_comments: bool,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(input: &str) -> Lexer {
+    pub fn new(input: &'a str) -> Lexer<'a> {
         let mut t = Lexer {
             input,
+            chars: input.chars(),
             position: 0,
             read_position: 0,
             ch: '\0',
+            with_comments: false,
         };
         t.read_char();
         t
     }
+
+    /// Configures whether comments are emitted as [TokenKind::LineComment]
+    /// and [TokenKind::BlockComment] tokens instead of being skipped like
+    /// whitespace. Disabled by default.
+    pub fn with_comments(mut self, with_comments: bool) -> Lexer<'a> {
+        self.with_comments = with_comments;
+        self
+    }
 }
 
 impl<'a> Lexer<'a> {
+    /// Advances the cursor by one char in O(1), using the retained `chars`
+    /// iterator rather than re-walking the input from the start.
     pub fn read_char(&mut self) {
-        if self.read_position >= self.input.len() {
-            self.ch = '\0';
-        } else {
-            self.ch = self.input.chars().nth(self.read_position).unwrap();
-        }
         self.position = self.read_position;
-        self.read_position += 1;
+        self.ch = self.chars.next().unwrap_or('\0');
+        self.read_position = self.input.len() - self.chars.as_str().len();
     }
 
-    pub fn consume_char(&mut self) -> String {
-        let ch = self.ch;
+    pub fn consume_char(&mut self) -> &'a str {
+        let start = self.position;
         self.read_char();
-        ch.to_string()
+        &self.input[start..self.position]
+    }
+
+    /// Returns the char at `read_position` without advancing the lexer.
+    pub fn peek_char(&self) -> char {
+        self.chars.clone().next().unwrap_or('\0')
     }
 
-    pub fn read_ident(&mut self) -> String {
+    /// Returns the char one past `peek_char`, without advancing the lexer.
+    pub fn peek_second_char(&self) -> char {
+        self.chars.clone().nth(1).unwrap_or('\0')
+    }
+
+    /// Consumes the current char plus the peeked char, returning both as a slice.
+    fn consume_two_chars(&mut self) -> &'a str {
+        let start = self.position;
+        self.read_char();
+        self.read_char();
+        &self.input[start..self.position]
+    }
+
+    pub fn read_ident(&mut self) -> &'a str {
         let position = self.position;
-        while self.is_letter() {
+        self.read_char(); // the start char was already checked by is_ident_start
+        while self.is_ident_continue() {
+            self.read_char();
+        }
+        &self.input[position..self.position]
+    }
+
+    /// Reads an integer or floating-point literal, returning which it turned
+    /// out to be alongside the lexed slice.
+    ///
+    /// `0x`/`0o`/`0b` prefixed literals are always integers and scanned as a
+    /// separate case, since their digits (and underscores) don't follow the
+    /// decimal/float grammar below. A trailing `.` only starts a fractional
+    /// part if a digit follows it, so in `1.max(2)` the `.` isn't swallowed
+    /// into the number. Likewise `e`/`E` only starts an exponent if a digit
+    /// (or a sign followed by a digit) follows.
+    pub fn read_number(&mut self) -> (token::LiteralKind, &'a str) {
+        let position = self.position;
+
+        if self.ch == '0' && matches!(self.peek_char(), 'x' | 'o' | 'b') {
+            self.read_char(); // consume '0'
+            self.read_char(); // consume the base prefix letter
+            while self.ch.is_ascii_hexdigit() || self.ch == '_' {
+                self.read_char();
+            }
+            return (
+                token::LiteralKind::Int,
+                &self.input[position..self.position],
+            );
+        }
+
+        while self.ch.is_ascii_digit() || self.ch == '_' {
             self.read_char();
         }
-        self.input[position..self.position].to_owned()
+
+        let mut kind = token::LiteralKind::Int;
+
+        if self.ch == '.' && self.peek_char().is_ascii_digit() {
+            kind = token::LiteralKind::Float;
+            self.read_char(); // consume '.'
+            while self.ch.is_ascii_digit() || self.ch == '_' {
+                self.read_char();
+            }
+        }
+
+        if matches!(self.ch, 'e' | 'E') && self.is_exponent_start() {
+            kind = token::LiteralKind::Float;
+            self.read_char(); // consume 'e'/'E'
+            if matches!(self.ch, '+' | '-') {
+                self.read_char();
+            }
+            while self.ch.is_ascii_digit() || self.ch == '_' {
+                self.read_char();
+            }
+        }
+
+        (kind, &self.input[position..self.position])
     }
 
-    pub fn read_number(&mut self) -> String {
+    /// Whether the lexer is positioned at `e`/`E` that actually begins an
+    /// exponent, i.e. is followed by a digit or a sign then a digit.
+    fn is_exponent_start(&self) -> bool {
+        match self.peek_char() {
+            c if c.is_ascii_digit() => true,
+            '+' | '-' => self.peek_second_char().is_ascii_digit(),
+            _ => false,
+        }
+    }
+
+    /// Reads a double-quoted string literal, starting at the opening `"`.
+    ///
+    /// Backslash escapes (`\n`, `\t`, `\\`, `\"`, `\0`, ...) are skipped over
+    /// rather than interpreted, so an escaped quote never terminates the
+    /// literal early. Returns the lexed slice alongside whether a real
+    /// closing `"` was found; an unterminated literal is still consumed up
+    /// to EOF, but the returned flag is `false`.
+    pub fn read_string(&mut self) -> (&'a str, bool) {
         let position = self.position;
-        while self.ch.is_ascii_digit() {
+        self.read_char(); // consume the opening '"'
+        while self.ch != '"' && self.ch != '\0' {
+            if self.ch == '\\' {
+                self.read_char();
+            }
             self.read_char();
         }
-        self.input[position..self.position].to_owned()
+        let terminated = self.ch == '"';
+        if terminated {
+            self.read_char(); // consume the closing '"'
+        }
+        (&self.input[position..self.position], terminated)
+    }
+
+    /// Reads a single-quoted char literal, starting at the opening `'`.
+    ///
+    /// Consumes exactly one (possibly escaped) char before the closing `'`.
+    /// Returns the lexed slice alongside whether it was well-formed: a
+    /// single char (escaped or not) followed by a closing `'`. An empty
+    /// (`''`), unterminated, or otherwise malformed literal is still
+    /// consumed as far as possible, but the returned flag is `false`.
+    pub fn read_char_literal(&mut self) -> (&'a str, bool) {
+        let position = self.position;
+        self.read_char(); // consume the opening '\''
+
+        if self.ch == '\'' || self.ch == '\0' {
+            // Empty ('') or truncated (') literal: there is no char to
+            // consume as the literal's content.
+            if self.ch == '\'' {
+                self.read_char();
+            }
+            return (&self.input[position..self.position], false);
+        }
+
+        if self.ch == '\\' && self.peek_char() != '\0' {
+            self.read_char();
+        }
+        self.read_char(); // consume the char itself
+
+        let terminated = self.ch == '\'';
+        if terminated {
+            self.read_char(); // consume the closing '\''
+        }
+        (&self.input[position..self.position], terminated)
     }
 
     pub fn skip_whitespace(&mut self) {
@@ -69,8 +213,87 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    pub fn is_letter(&self) -> bool {
-        self.ch.is_alphabetic() || self.ch == '_'
+    /// Skips whitespace and, unless `with_comments` is set, comments as
+    /// well, leaving the lexer positioned at the next real token (or EOF).
+    fn skip_trivia(&mut self) {
+        let _ = self.skip_trivia_checked();
+    }
+
+    /// Same as [`Lexer::skip_trivia`], but reports an unterminated block
+    /// comment instead of silently consuming it to EOF.
+    fn skip_trivia_checked(&mut self) -> Result<(), LexError> {
+        loop {
+            self.skip_whitespace();
+
+            if self.with_comments {
+                return Ok(());
+            }
+
+            match (self.ch, self.peek_char()) {
+                ('/', '/') => {
+                    self.read_line_comment();
+                }
+                ('/', '*') => {
+                    let position = self.position;
+                    let (_, terminated) = self.read_block_comment();
+                    if !terminated {
+                        return Err(LexError::UnexpectedEndOfFile { position });
+                    }
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    /// Reads a `//` line comment, starting at the first `/`, consuming up
+    /// to (but not including) the next newline or EOF.
+    pub fn read_line_comment(&mut self) -> &'a str {
+        let position = self.position;
+        while self.ch != '\n' && self.ch != '\0' {
+            self.read_char();
+        }
+        &self.input[position..self.position]
+    }
+
+    /// Reads a `/* ... */` block comment, starting at the opening `/`.
+    ///
+    /// Nested block comments are supported: a `/*` inside the comment
+    /// increments a depth counter and a matching `*/` decrements it, so the
+    /// comment only ends once the outermost `*/` is reached. Returns the
+    /// lexed slice alongside whether `depth` actually reached 0; an
+    /// unterminated comment is still consumed up to EOF, but the returned
+    /// flag is `false`.
+    pub fn read_block_comment(&mut self) -> (&'a str, bool) {
+        let position = self.position;
+        self.read_char(); // consume '/'
+        self.read_char(); // consume '*'
+        let mut depth = 1;
+        while depth > 0 && self.ch != '\0' {
+            if self.ch == '/' && self.peek_char() == '*' {
+                self.read_char();
+                self.read_char();
+                depth += 1;
+            } else if self.ch == '*' && self.peek_char() == '/' {
+                self.read_char();
+                self.read_char();
+                depth -= 1;
+            } else {
+                self.read_char();
+            }
+        }
+        (&self.input[position..self.position], depth == 0)
+    }
+
+    /// Whether the current char can start an identifier, per Unicode's
+    /// `XID_Start` (plus `_`, as rustc does, since `XID_Start` excludes it).
+    pub fn is_ident_start(&self) -> bool {
+        self.ch == '_' || self.ch.is_xid_start()
+    }
+
+    /// Whether the current char can continue an identifier, per Unicode's
+    /// `XID_Continue`.
+    pub fn is_ident_continue(&self) -> bool {
+        self.ch.is_xid_continue()
     }
 
     pub fn is_digit(&self) -> bool {
@@ -78,55 +301,171 @@ impl<'a> Lexer<'a> {
     }
 }
 
+impl<'a> Lexer<'a> {
+    /// Scans a single token starting at the current char, which must not be
+    /// whitespace, a comment (when skipped), or EOF. Returns the token's
+    /// kind, its literal slice, and whether it was well-formed (always
+    /// `true`, except for an unterminated/malformed string, char, or block
+    /// comment), without computing its span, so both the infallible
+    /// [`Iterator`] impl and [`Lexer::try_next_token`] can wrap it with
+    /// their own span/error handling.
+    fn scan_token(&mut self) -> (TokenKind, &'a str, bool) {
+        match self.ch {
+            ';' => (TokenKind::Semi, self.consume_char(), true),
+            ',' => (TokenKind::Comma, self.consume_char(), true),
+            '.' if self.peek_char() == '.' => (TokenKind::DotDot, self.consume_two_chars(), true),
+            '.' => (TokenKind::Dot, self.consume_char(), true),
+            '(' => (TokenKind::OpenParen, self.consume_char(), true),
+            ')' => (TokenKind::CloseParen, self.consume_char(), true),
+            '{' => (TokenKind::OpenBrace, self.consume_char(), true),
+            '}' => (TokenKind::CloseBrace, self.consume_char(), true),
+            '[' => (TokenKind::OpenBracket, self.consume_char(), true),
+            ']' => (TokenKind::CloseBracket, self.consume_char(), true),
+            '@' => (TokenKind::At, self.consume_char(), true),
+            '#' => (TokenKind::Pound, self.consume_char(), true),
+            '~' => (TokenKind::Tilde, self.consume_char(), true),
+            '!' if self.peek_char() == '=' => (TokenKind::Ne, self.consume_two_chars(), true),
+            '!' => (TokenKind::Bang, self.consume_char(), true),
+            '=' if self.peek_char() == '=' => (TokenKind::EqEq, self.consume_two_chars(), true),
+            '=' => (TokenKind::Eq, self.consume_char(), true),
+            '+' if self.peek_char() == '=' => (TokenKind::PlusEq, self.consume_two_chars(), true),
+            '+' => (TokenKind::Plus, self.consume_char(), true),
+            '-' if self.peek_char() == '=' => (TokenKind::MinusEq, self.consume_two_chars(), true),
+            '-' if self.peek_char() == '>' => (TokenKind::Arrow, self.consume_two_chars(), true),
+            '-' => (TokenKind::Minus, self.consume_char(), true),
+            '*' => (TokenKind::Star, self.consume_char(), true),
+            '/' if self.peek_char() == '/' => {
+                (TokenKind::LineComment, self.read_line_comment(), true)
+            }
+            '/' if self.peek_char() == '*' => {
+                let (comment, terminated) = self.read_block_comment();
+                (TokenKind::BlockComment, comment, terminated)
+            }
+            '/' => (TokenKind::Slash, self.consume_char(), true),
+            '<' if self.peek_char() == '=' => (TokenKind::Le, self.consume_two_chars(), true),
+            '<' => (TokenKind::Lt, self.consume_char(), true),
+            '>' if self.peek_char() == '=' => (TokenKind::Ge, self.consume_two_chars(), true),
+            '>' => (TokenKind::Gt, self.consume_char(), true),
+            '&' if self.peek_char() == '&' => (TokenKind::AndAnd, self.consume_two_chars(), true),
+            '&' => (TokenKind::And, self.consume_char(), true),
+            '|' if self.peek_char() == '|' => (TokenKind::OrOr, self.consume_two_chars(), true),
+            '|' => (TokenKind::Or, self.consume_char(), true),
+            '^' => (TokenKind::Caret, self.consume_char(), true),
+            ':' if self.peek_char() == ':' => {
+                (TokenKind::ColonColon, self.consume_two_chars(), true)
+            }
+            ':' => (TokenKind::Colon, self.consume_char(), true),
+            '?' => (TokenKind::Question, self.consume_char(), true),
+            '$' => (TokenKind::Dollar, self.consume_char(), true),
+            '%' => (TokenKind::Percent, self.consume_char(), true),
+            '"' => {
+                let (literal, terminated) = self.read_string();
+                (
+                    TokenKind::Literal {
+                        kind: token::LiteralKind::Str,
+                    },
+                    literal,
+                    terminated,
+                )
+            }
+            '\'' => {
+                let (literal, terminated) = self.read_char_literal();
+                (
+                    TokenKind::Literal {
+                        kind: token::LiteralKind::Char,
+                    },
+                    literal,
+                    terminated,
+                )
+            }
+            _ if self.is_ident_start() => {
+                let ident = self.read_ident();
+                let kind = token::lookup_keyword(ident).unwrap_or(TokenKind::Ident);
+                (kind, ident, true)
+            }
+            _ if self.is_digit() => {
+                let (lit_kind, number) = self.read_number();
+                (TokenKind::Literal { kind: lit_kind }, number, true)
+            }
+            _ => (TokenKind::Unknown, self.consume_char(), true),
+        }
+    }
+
+    /// Fallible counterpart to the [`Iterator`] impl: scans and returns the
+    /// next token, or the first [LexError] encountered. Unlike the
+    /// [`Iterator`] impl, an unterminated/malformed string literal, char
+    /// literal, or block comment is reported rather than silently accepted
+    /// or consumed to EOF.
+    pub fn try_next_token(&mut self) -> Result<Option<Token<'a>>, LexError> {
+        self.skip_trivia_checked()?;
+
+        if self.ch == '\0' {
+            return Ok(None);
+        }
+
+        let start = self.position;
+        let (kind, literal, terminated) = self.scan_token();
+
+        if !terminated {
+            match kind {
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Str,
+                } => return Err(LexError::UnterminatedString { position: start }),
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Char,
+                } => {
+                    return Err(LexError::ExpectedCharacter {
+                        expected: '\'',
+                        actual: self.ch,
+                        position: self.position,
+                    });
+                }
+                TokenKind::BlockComment => {
+                    return Err(LexError::UnexpectedEndOfFile { position: start });
+                }
+                _ => {}
+            }
+        }
+
+        let span = Span {
+            start,
+            end: self.position,
+        };
+
+        Ok(Some(Token::new(kind, literal, span)))
+    }
+}
+
+/// Lexes all of `input`, returning every token or the first [LexError]
+/// encountered along with its position.
+pub fn lex(input: &str) -> Result<Vec<Token<'_>>, LexError> {
+    let mut lexer = Lexer::new(input);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.try_next_token()? {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
 impl<'a> Iterator for Lexer<'a> {
-    type Item = Token;
+    type Item = Token<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
+        self.skip_trivia();
 
         if self.ch == '\0' {
             return None;
         }
 
-        let token = match self.ch {
-            ';' => Token::new(TokenKind::Semi, self.consume_char()),
-            ',' => Token::new(TokenKind::Comma, self.consume_char()),
-            '.' => Token::new(TokenKind::Dot, self.consume_char()),
-            '(' => Token::new(TokenKind::OpenParen, self.consume_char()),
-            ')' => Token::new(TokenKind::CloseParen, self.consume_char()),
-            '{' => Token::new(TokenKind::OpenBrace, self.consume_char()),
-            '}' => Token::new(TokenKind::CloseBrace, self.consume_char()),
-            '[' => Token::new(TokenKind::OpenBracket, self.consume_char()),
-            ']' => Token::new(TokenKind::CloseBracket, self.consume_char()),
-            '@' => Token::new(TokenKind::At, self.consume_char()),
-            '#' => Token::new(TokenKind::Pound, self.consume_char()),
-            '~' => Token::new(TokenKind::Tilde, self.consume_char()),
-            '!' => Token::new(TokenKind::Bang, self.consume_char()),
-            '=' => Token::new(TokenKind::Eq, self.consume_char()),
-            '+' => Token::new(TokenKind::Plus, self.consume_char()),
-            '-' => Token::new(TokenKind::Minus, self.consume_char()),
-            '*' => Token::new(TokenKind::Star, self.consume_char()),
-            '/' => Token::new(TokenKind::Slash, self.consume_char()),
-            '<' => Token::new(TokenKind::Lt, self.consume_char()),
-            '>' => Token::new(TokenKind::Gt, self.consume_char()),
-            '&' => Token::new(TokenKind::And, self.consume_char()),
-            '|' => Token::new(TokenKind::Or, self.consume_char()),
-            '^' => Token::new(TokenKind::Caret, self.consume_char()),
-            ':' => Token::new(TokenKind::Colon, self.consume_char()),
-            '?' => Token::new(TokenKind::Question, self.consume_char()),
-            '$' => Token::new(TokenKind::Dollar, self.consume_char()),
-            '%' => Token::new(TokenKind::Percent, self.consume_char()),
-            _ if self.is_letter() => Token::new(TokenKind::Ident, self.read_ident()),
-            _ if self.is_digit() => Token::new(
-                TokenKind::Literal {
-                    kind: token::LiteralKind::Int,
-                },
-                self.read_number(),
-            ),
-            _ => Token::new(TokenKind::Unknown, self.consume_char()),
+        let start = self.position;
+        let (kind, literal, _terminated) = self.scan_token();
+
+        let span = Span {
+            start,
+            end: self.position,
         };
 
-        Some(token)
+        Some(Token::new(kind, literal, span))
     }
 }
 
@@ -134,45 +473,48 @@ impl<'a> Iterator for Lexer<'a> {
 mod test {
     use super::*;
 
+    /// Asserts that `lexer` yields exactly the given `(kind, literal)` pairs, in order.
+    fn assert_tokens(lexer: Lexer, expected: Vec<(TokenKind, &str)>) {
+        let tokens: Vec<_> = lexer.collect();
+        let actual: Vec<_> = tokens.iter().map(|t| (t.kind, t.literal)).collect();
+        assert_eq!(actual, expected);
+    }
+
     #[test]
     fn test_single_char_token() {
         let input = r#"
-            ;,.(){}[]@#~!=+-*/<>&|^:?$%
+            ;,.(){}[]@#~=!+-*/<>&|^:?$%
         "#;
-        let mut tokenizer = Lexer::new(input);
         let expected = vec![
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::Comma, ",".to_owned()),
-            Token::new(TokenKind::Dot, ".".to_owned()),
-            Token::new(TokenKind::OpenParen, "(".to_owned()),
-            Token::new(TokenKind::CloseParen, ")".to_owned()),
-            Token::new(TokenKind::OpenBrace, "{".to_owned()),
-            Token::new(TokenKind::CloseBrace, "}".to_owned()),
-            Token::new(TokenKind::OpenBracket, "[".to_owned()),
-            Token::new(TokenKind::CloseBracket, "]".to_owned()),
-            Token::new(TokenKind::At, "@".to_owned()),
-            Token::new(TokenKind::Pound, "#".to_owned()),
-            Token::new(TokenKind::Tilde, "~".to_owned()),
-            Token::new(TokenKind::Bang, "!".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(TokenKind::Plus, "+".to_owned()),
-            Token::new(TokenKind::Minus, "-".to_owned()),
-            Token::new(TokenKind::Star, "*".to_owned()),
-            Token::new(TokenKind::Slash, "/".to_owned()),
-            Token::new(TokenKind::Lt, "<".to_owned()),
-            Token::new(TokenKind::Gt, ">".to_owned()),
-            Token::new(TokenKind::And, "&".to_owned()),
-            Token::new(TokenKind::Or, "|".to_owned()),
-            Token::new(TokenKind::Caret, "^".to_owned()),
-            Token::new(TokenKind::Colon, ":".to_owned()),
-            Token::new(TokenKind::Question, "?".to_owned()),
-            Token::new(TokenKind::Dollar, "$".to_owned()),
-            Token::new(TokenKind::Percent, "%".to_owned()),
+            (TokenKind::Semi, ";"),
+            (TokenKind::Comma, ","),
+            (TokenKind::Dot, "."),
+            (TokenKind::OpenParen, "("),
+            (TokenKind::CloseParen, ")"),
+            (TokenKind::OpenBrace, "{"),
+            (TokenKind::CloseBrace, "}"),
+            (TokenKind::OpenBracket, "["),
+            (TokenKind::CloseBracket, "]"),
+            (TokenKind::At, "@"),
+            (TokenKind::Pound, "#"),
+            (TokenKind::Tilde, "~"),
+            (TokenKind::Eq, "="),
+            (TokenKind::Bang, "!"),
+            (TokenKind::Plus, "+"),
+            (TokenKind::Minus, "-"),
+            (TokenKind::Star, "*"),
+            (TokenKind::Slash, "/"),
+            (TokenKind::Lt, "<"),
+            (TokenKind::Gt, ">"),
+            (TokenKind::And, "&"),
+            (TokenKind::Or, "|"),
+            (TokenKind::Caret, "^"),
+            (TokenKind::Colon, ":"),
+            (TokenKind::Question, "?"),
+            (TokenKind::Dollar, "$"),
+            (TokenKind::Percent, "%"),
         ];
-        for e in expected {
-            let next = tokenizer.next();
-            assert_eq!(next.unwrap(), e);
-        }
+        assert_tokens(Lexer::new(input), expected);
     }
 
     #[test]
@@ -180,23 +522,19 @@ mod test {
         let input = r#"
             let five = 5;
         "#;
-        let mut lexer = Lexer::new(input);
         let expected = vec![
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "five".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "five"),
+            (TokenKind::Eq, "="),
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "5".to_owned(),
+                "5",
             ),
-            Token::new(TokenKind::Semi, ";".to_owned()),
+            (TokenKind::Semi, ";"),
         ];
-        for e in expected {
-            let next = lexer.next();
-            assert_eq!(next.unwrap(), e);
-        }
+        assert_tokens(Lexer::new(input), expected);
     }
 
     #[test]
@@ -209,119 +547,557 @@ mod test {
             };
             let result = add(five, ten);
         "#;
-        let mut lexer = Lexer::new(input);
         let expected = vec![
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "five".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "five"),
+            (TokenKind::Eq, "="),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Float,
+                },
+                "5.0",
+            ),
+            (TokenKind::Semi, ";"),
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "ten"),
+            (TokenKind::Eq, "="),
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "5".to_owned(),
+                "10",
             ),
-            Token::new(TokenKind::Dot, ".".to_owned()),
-            Token::new(
+            (TokenKind::Semi, ";"),
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "add"),
+            (TokenKind::Eq, "="),
+            (TokenKind::Fn, "fn"),
+            (TokenKind::OpenParen, "("),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Comma, ","),
+            (TokenKind::Ident, "y"),
+            (TokenKind::CloseParen, ")"),
+            (TokenKind::OpenBrace, "{"),
+            (TokenKind::Return, "return"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Plus, "+"),
+            (TokenKind::Ident, "y"),
+            (TokenKind::Semi, ";"),
+            (TokenKind::CloseBrace, "}"),
+            (TokenKind::Semi, ";"),
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "result"),
+            (TokenKind::Eq, "="),
+            (TokenKind::Ident, "add"),
+            (TokenKind::OpenParen, "("),
+            (TokenKind::Ident, "five"),
+            (TokenKind::Comma, ","),
+            (TokenKind::Ident, "ten"),
+            (TokenKind::CloseParen, ")"),
+            (TokenKind::Semi, ";"),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_while_loop() {
+        let input = r#"
+            let i = 0;
+            while (i < 10) {
+                i = i + 1;
+            }
+        "#;
+        let expected = vec![
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "i"),
+            (TokenKind::Eq, "="),
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "0".to_owned(),
+                "0",
             ),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "ten".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(
+            (TokenKind::Semi, ";"),
+            (TokenKind::While, "while"),
+            (TokenKind::OpenParen, "("),
+            (TokenKind::Ident, "i"),
+            (TokenKind::Lt, "<"),
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "10".to_owned(),
+                "10",
             ),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "add".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(TokenKind::Ident, "fn".to_owned()),
-            Token::new(TokenKind::OpenParen, "(".to_owned()),
-            Token::new(TokenKind::Ident, "x".to_owned()),
-            Token::new(TokenKind::Comma, ",".to_owned()),
-            Token::new(TokenKind::Ident, "y".to_owned()),
-            Token::new(TokenKind::CloseParen, ")".to_owned()),
-            Token::new(TokenKind::OpenBrace, "{".to_owned()),
-            Token::new(TokenKind::Ident, "return".to_owned()),
-            Token::new(TokenKind::Ident, "x".to_owned()),
-            Token::new(TokenKind::Plus, "+".to_owned()),
-            Token::new(TokenKind::Ident, "y".to_owned()),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::CloseBrace, "}".to_owned()),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "result".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(TokenKind::Ident, "add".to_owned()),
-            Token::new(TokenKind::OpenParen, "(".to_owned()),
-            Token::new(TokenKind::Ident, "five".to_owned()),
-            Token::new(TokenKind::Comma, ",".to_owned()),
-            Token::new(TokenKind::Ident, "ten".to_owned()),
-            Token::new(TokenKind::CloseParen, ")".to_owned()),
-            Token::new(TokenKind::Semi, ";".to_owned()),
+            (TokenKind::CloseParen, ")"),
+            (TokenKind::OpenBrace, "{"),
+            (TokenKind::Ident, "i"),
+            (TokenKind::Eq, "="),
+            (TokenKind::Ident, "i"),
+            (TokenKind::Plus, "+"),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "1",
+            ),
+            (TokenKind::Semi, ";"),
+            (TokenKind::CloseBrace, "}"),
         ];
+        assert_tokens(Lexer::new(input), expected);
+    }
 
-        for e in expected {
-            let next = lexer.next();
-            assert_eq!(next.unwrap(), e);
-        }
+    #[test]
+    fn test_multi_char_operators() {
+        let input = r#"
+            == != <= >= && || += -= -> :: ..
+        "#;
+        let expected = vec![
+            (TokenKind::EqEq, "=="),
+            (TokenKind::Ne, "!="),
+            (TokenKind::Le, "<="),
+            (TokenKind::Ge, ">="),
+            (TokenKind::AndAnd, "&&"),
+            (TokenKind::OrOr, "||"),
+            (TokenKind::PlusEq, "+="),
+            (TokenKind::MinusEq, "-="),
+            (TokenKind::Arrow, "->"),
+            (TokenKind::ColonColon, "::"),
+            (TokenKind::DotDot, ".."),
+        ];
+        assert_tokens(Lexer::new(input), expected);
     }
 
     #[test]
-    fn test_while_loop() {
+    fn test_single_char_not_mismatched_as_multi_char() {
         let input = r#"
-            let i = 0;
-            while (i < 10) {
-                i = i + 1;
-            }
+            = ! + - < > & | : .
+        "#;
+        let expected = vec![
+            (TokenKind::Eq, "="),
+            (TokenKind::Bang, "!"),
+            (TokenKind::Plus, "+"),
+            (TokenKind::Minus, "-"),
+            (TokenKind::Lt, "<"),
+            (TokenKind::Gt, ">"),
+            (TokenKind::And, "&"),
+            (TokenKind::Or, "|"),
+            (TokenKind::Colon, ":"),
+            (TokenKind::Dot, "."),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_string_and_char_literals() {
+        let input = r#"
+            "hello, world" "escaped \" quote" 'a' '\n'
         "#;
-        let mut lexer = Lexer::new(input);
         let expected = vec![
-            Token::new(TokenKind::Ident, "let".to_owned()),
-            Token::new(TokenKind::Ident, "i".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Str,
+                },
+                r#""hello, world""#,
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Str,
+                },
+                r#""escaped \" quote""#,
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Char,
+                },
+                "'a'",
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Char,
+                },
+                r"'\n'",
+            ),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_unterminated_string_literal_consumes_to_eof() {
+        let input = r#""unterminated"#;
+        let expected = vec![(
+            TokenKind::Literal {
+                kind: token::LiteralKind::Str,
+            },
+            r#""unterminated"#,
+        )];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_keywords_and_bool_literals() {
+        let input = r#"
+            let fn while if else return true false notakeyword
+        "#;
+        let expected = vec![
+            (TokenKind::Let, "let"),
+            (TokenKind::Fn, "fn"),
+            (TokenKind::While, "while"),
+            (TokenKind::If, "if"),
+            (TokenKind::Else, "else"),
+            (TokenKind::Return, "return"),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Bool,
+                },
+                "true",
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Bool,
+                },
+                "false",
+            ),
+            (TokenKind::Ident, "notakeyword"),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_integer_literal_bases() {
+        let input = "0x1A_2b 0o17 0b1010_1010 42";
+        let expected = vec![
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "0".to_owned(),
+                "0x1A_2b",
             ),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::Ident, "while".to_owned()),
-            Token::new(TokenKind::OpenParen, "(".to_owned()),
-            Token::new(TokenKind::Ident, "i".to_owned()),
-            Token::new(TokenKind::Lt, "<".to_owned()),
-            Token::new(
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "10".to_owned(),
+                "0o17",
             ),
-            Token::new(TokenKind::CloseParen, ")".to_owned()),
-            Token::new(TokenKind::OpenBrace, "{".to_owned()),
-            Token::new(TokenKind::Ident, "i".to_owned()),
-            Token::new(TokenKind::Eq, "=".to_owned()),
-            Token::new(TokenKind::Ident, "i".to_owned()),
-            Token::new(TokenKind::Plus, "+".to_owned()),
-            Token::new(
+            (
                 TokenKind::Literal {
                     kind: token::LiteralKind::Int,
                 },
-                "1".to_owned(),
+                "0b1010_1010",
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "42",
             ),
-            Token::new(TokenKind::Semi, ";".to_owned()),
-            Token::new(TokenKind::CloseBrace, "}".to_owned()),
         ];
+        assert_tokens(Lexer::new(input), expected);
+    }
 
-        for e in expected {
-            let next = lexer.next();
-            assert_eq!(next.unwrap(), e);
+    #[test]
+    fn test_integer_literal_with_digit_separators() {
+        let input = "1_000_000";
+        let expected = vec![(
+            TokenKind::Literal {
+                kind: token::LiteralKind::Int,
+            },
+            "1_000_000",
+        )];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_float_literals() {
+        let input = "3.14 1. 1e10 2.5e-3 1E+2";
+        let expected = vec![
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Float,
+                },
+                "3.14",
+            ),
+            // A trailing '.' with no following digit does not start a
+            // fractional part, so "1." lexes as an int followed by a dot.
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "1",
+            ),
+            (TokenKind::Dot, "."),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Float,
+                },
+                "1e10",
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Float,
+                },
+                "2.5e-3",
+            ),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Float,
+                },
+                "1E+2",
+            ),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_dot_followed_by_non_digit_is_not_a_float() {
+        let input = "x.len";
+        let expected = vec![
+            (TokenKind::Ident, "x"),
+            (TokenKind::Dot, "."),
+            (TokenKind::Ident, "len"),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_comments_skipped_by_default() {
+        let input = r#"
+            let x = 1; // a line comment
+            /* a block
+               comment */
+            let y = /* inline */ 2;
+        "#;
+        let expected = vec![
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "x"),
+            (TokenKind::Eq, "="),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "1",
+            ),
+            (TokenKind::Semi, ";"),
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "y"),
+            (TokenKind::Eq, "="),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "2",
+            ),
+            (TokenKind::Semi, ";"),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_nested_block_comments_skipped() {
+        let input = "/* outer /* inner */ still outer */ 1";
+        let expected = vec![(
+            TokenKind::Literal {
+                kind: token::LiteralKind::Int,
+            },
+            "1",
+        )];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_unterminated_block_comment_consumes_to_eof() {
+        let input = "1 /* unterminated";
+        let expected = vec![(
+            TokenKind::Literal {
+                kind: token::LiteralKind::Int,
+            },
+            "1",
+        )];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_with_comments_emits_comment_tokens() {
+        let input = "1 // line\n/* block */ 2";
+        let expected = vec![
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "1",
+            ),
+            (TokenKind::LineComment, "// line"),
+            (TokenKind::BlockComment, "/* block */"),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "2",
+            ),
+        ];
+        assert_tokens(Lexer::new(input).with_comments(true), expected);
+    }
+
+    #[test]
+    fn test_unicode_identifiers() {
+        let input = "let café = 1; let Ω = 2;";
+        let expected = vec![
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "café"),
+            (TokenKind::Eq, "="),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "1",
+            ),
+            (TokenKind::Semi, ";"),
+            (TokenKind::Let, "let"),
+            (TokenKind::Ident, "Ω"),
+            (TokenKind::Eq, "="),
+            (
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                "2",
+            ),
+            (TokenKind::Semi, ";"),
+        ];
+        assert_tokens(Lexer::new(input), expected);
+    }
+
+    #[test]
+    fn test_multi_byte_chars_do_not_corrupt_spans() {
+        let input = "let café = 1;";
+        let lexer = Lexer::new(input);
+        let spans: Vec<_> = lexer.map(|t| (t.literal, t.span)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("let", Span { start: 0, end: 3 }),
+                // "café" is 4 chars but 5 bytes ('é' is 2 bytes in UTF-8).
+                ("café", Span { start: 4, end: 9 }),
+                ("=", Span { start: 10, end: 11 }),
+                ("1", Span { start: 12, end: 13 }),
+                (";", Span { start: 13, end: 14 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans_exclude_skipped_whitespace() {
+        let input = "  let   five = 5 ;";
+        let lexer = Lexer::new(input);
+        let spans: Vec<_> = lexer.map(|t| (t.literal, t.span)).collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("let", Span { start: 2, end: 5 }),
+                ("five", Span { start: 8, end: 12 }),
+                ("=", Span { start: 13, end: 14 }),
+                ("5", Span { start: 15, end: 16 }),
+                (";", Span { start: 17, end: 18 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_collects_all_tokens() {
+        let tokens = lex("let x = 1;").unwrap();
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Let,
+                TokenKind::Ident,
+                TokenKind::Eq,
+                TokenKind::Literal {
+                    kind: token::LiteralKind::Int,
+                },
+                TokenKind::Semi,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_reports_unterminated_string() {
+        let err = lex(r#"let x = "unterminated;"#).unwrap_err();
+        assert_eq!(err, LexError::UnterminatedString { position: 8 });
+    }
+
+    #[test]
+    fn test_lex_reports_unterminated_char_literal() {
+        let err = lex("'a").unwrap_err();
+        assert_eq!(
+            err,
+            LexError::ExpectedCharacter {
+                expected: '\'',
+                actual: '\0',
+                position: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lex_reports_empty_char_literal() {
+        let err = lex("''").unwrap_err();
+        assert_eq!(
+            err,
+            LexError::ExpectedCharacter {
+                expected: '\'',
+                actual: '\0',
+                position: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_lex_reports_unterminated_block_comment() {
+        let err = lex("1 /* oops").unwrap_err();
+        assert_eq!(err, LexError::UnexpectedEndOfFile { position: 2 });
+    }
+
+    #[test]
+    fn test_lex_reports_nested_unterminated_block_comment() {
+        // The inner "*/" closes the inner comment, but the outer one never
+        // closes, so this must still be reported as unterminated.
+        let err = lex("1 /* a /* b */").unwrap_err();
+        assert_eq!(err, LexError::UnexpectedEndOfFile { position: 2 });
+    }
+
+    #[test]
+    fn test_lex_reports_string_with_trailing_escaped_quote_as_unterminated() {
+        // `"\"` is a quote, a backslash, and a quote: the backslash escapes
+        // the final quote, so there is no real closing quote.
+        let err = lex(r#""\""#).unwrap_err();
+        assert_eq!(err, LexError::UnterminatedString { position: 0 });
+    }
+
+    #[test]
+    fn test_lex_reports_char_literal_with_trailing_escaped_quote_as_unterminated() {
+        // `'\'` is a quote, a backslash, and a quote: the backslash escapes
+        // the final quote, so there is no real closing quote.
+        let err = lex(r"'\'").unwrap_err();
+        assert_eq!(
+            err,
+            LexError::ExpectedCharacter {
+                expected: '\'',
+                actual: '\0',
+                position: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_try_next_token_matches_iterator_on_valid_input() {
+        let input = "let x = 1;";
+        let iter_tokens: Vec<_> = Lexer::new(input).collect();
+
+        let mut lexer = Lexer::new(input);
+        let mut fallible_tokens = Vec::new();
+        while let Some(token) = lexer.try_next_token().unwrap() {
+            fallible_tokens.push(token);
         }
+
+        assert_eq!(iter_tokens, fallible_tokens);
     }
 }
@@ -1,20 +1,40 @@
 #[derive(Debug, PartialEq)]
-pub struct Token {
+pub struct Token<'a> {
     pub kind: TokenKind,
-    pub literal: String,
+    pub literal: &'a str,
+    pub span: Span,
 }
 
-impl Token {
-    pub fn new(kind: TokenKind, literal: String) -> Token {
-        Token { kind, literal }
+impl<'a> Token<'a> {
+    pub fn new(kind: TokenKind, literal: &'a str, span: Span) -> Token<'a> {
+        Token {
+            kind,
+            literal,
+            span,
+        }
     }
 }
 
+/// A half-open range of byte offsets `[start, end)` into the lexer's input,
+/// excluding any whitespace skipped before the token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
 /// Enum representing common lexeme types.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenKind {
     WhiteSpace,
 
+    /// "// line comment", emitted only when the lexer is built `with_comments(true)`.
+    LineComment,
+    /// "/* block comment */", emitted only when the lexer is built `with_comments(true)`.
+    ///
+    /// May be nested, e.g. "/* outer /* inner */ outer */".
+    BlockComment,
+
     /// "ident" or "continue"
     ///
     /// At this step, keywords are also considered identifiers.
@@ -81,6 +101,44 @@ pub enum TokenKind {
     /// "%"
     Percent,
 
+    // Two-char tokens:
+    /// "=="
+    EqEq,
+    /// "!="
+    Ne,
+    /// "<="
+    Le,
+    /// ">="
+    Ge,
+    /// "&&"
+    AndAnd,
+    /// "||"
+    OrOr,
+    /// "+="
+    PlusEq,
+    /// "-="
+    MinusEq,
+    /// "->"
+    Arrow,
+    /// "::"
+    ColonColon,
+    /// ".."
+    DotDot,
+
+    // Keywords:
+    /// "let"
+    Let,
+    /// "fn"
+    Fn,
+    /// "while"
+    While,
+    /// "if"
+    If,
+    /// "else"
+    Else,
+    /// "return"
+    Return,
+
     /// Unknown token, not expected by the lexer, e.g. "â„–"
     Unknown,
 
@@ -88,6 +146,27 @@ pub enum TokenKind {
     Eof,
 }
 
+/// Maps keyword lexemes to their dedicated [TokenKind], so `read_ident` never
+/// needs to fall back to string-comparing against a keyword list. `true` and
+/// `false` map straight to `Literal { kind: Bool }` rather than a bare keyword
+/// token, since the parser wants them as literals.
+static KEYWORDS: phf::Map<&'static str, TokenKind> = phf::phf_map! {
+    "let" => TokenKind::Let,
+    "fn" => TokenKind::Fn,
+    "while" => TokenKind::While,
+    "if" => TokenKind::If,
+    "else" => TokenKind::Else,
+    "return" => TokenKind::Return,
+    "true" => TokenKind::Literal { kind: LiteralKind::Bool },
+    "false" => TokenKind::Literal { kind: LiteralKind::Bool },
+};
+
+/// Looks up `ident` in the keyword table, returning its dedicated
+/// [TokenKind] if it is a keyword.
+pub fn lookup_keyword(ident: &str) -> Option<TokenKind> {
+    KEYWORDS.get(ident).copied()
+}
+
 /// Enum representing the literal types supported by the lexer.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LiteralKind {